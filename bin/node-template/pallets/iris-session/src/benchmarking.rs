@@ -0,0 +1,98 @@
+//! Benchmarking setup for pallet-iris-session
+
+use super::*;
+use crate::Pallet as IrisSession;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+
+const SEED: u32 = 0;
+
+/// grows `Validators`/`ApprovedValidators` to `v` entries, each already bonded, so
+/// `add_validator`/`remove_validator`, which scan the existing set, are benchmarked at
+/// realistic sizes
+fn seed_validators<T: Config>(v: u32) -> Vec<T::AccountId> {
+	let validators: Vec<T::AccountId> = (0..v).map(|i| account("validator", i, SEED)).collect();
+	for validator in validators.iter() {
+		<ValidatorBonds<T>>::insert(validator, T::ValidatorBond::get());
+	}
+	<Validators<T>>::put(validators.clone());
+	<ApprovedValidators<T>>::put(validators.clone());
+	validators
+}
+
+benchmarks! {
+	add_validator {
+		let v in 1 .. 1000;
+		seed_validators::<T>(v);
+		let new_validator: T::AccountId = account("new_validator", 0, SEED);
+		T::Currency::make_free_balance_be(&new_validator, T::ValidatorBond::get() * 2u32.into());
+	}: _(RawOrigin::Root, new_validator.clone())
+	verify {
+		assert!(<Validators<T>>::get().contains(&new_validator));
+	}
+
+	remove_validator {
+		let v in (T::MinAuthorities::get() + 1) .. 1000;
+		let validators = seed_validators::<T>(v);
+		let target = validators[0].clone();
+	}: _(RawOrigin::Root, target.clone())
+	verify {
+		assert!(!<Validators<T>>::get().contains(&target));
+	}
+
+	add_validator_again {
+		let v in 1 .. 1000;
+		let validators = seed_validators::<T>(v);
+		let target = validators[0].clone();
+		<Validators<T>>::mutate(|vs| vs.retain(|x| x != &target));
+	}: _(RawOrigin::Signed(target.clone()), target.clone())
+	verify {
+		assert!(<Validators<T>>::get().contains(&target));
+	}
+
+	go_offline {
+		let validators = seed_validators::<T>(T::MinAuthorities::get() + 1);
+		let caller = validators[0].clone();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(<OutgoingValidators<T>>::get().contains(&caller));
+	}
+
+	go_online {
+		let validators = seed_validators::<T>(T::MinAuthorities::get() + 1);
+		let caller = validators[0].clone();
+		<Validators<T>>::mutate(|vs| vs.retain(|x| x != &caller));
+		<OutgoingValidators<T>>::mutate(|v| v.push(caller.clone()));
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(<Validators<T>>::get().contains(&caller));
+	}
+
+	request_join_storage_pool {
+		let caller: T::AccountId = whitelisted_caller();
+		let pool_owner_lookup = T::Lookup::unlookup(caller.clone());
+		let pool_id: T::AssetId = Default::default();
+		T::Currency::make_free_balance_be(&caller, T::PoolBond::get() * 2u32.into());
+	}: _(RawOrigin::Signed(caller.clone()), pool_owner_lookup, pool_id.clone())
+	verify {
+		assert_eq!(<PoolBonds<T>>::get(caller, pool_id), T::PoolBond::get());
+	}
+
+	request_exit_storage_pool {
+		let caller: T::AccountId = whitelisted_caller();
+		let pool_id: T::AssetId = Default::default();
+		<PoolBonds<T>>::insert(&caller, &pool_id, T::PoolBond::get());
+	}: _(RawOrigin::Signed(caller.clone()), pool_id.clone())
+	verify {
+		assert!(<UnbondingRequests<T>>::contains_key((caller, pool_id)));
+	}
+
+	set_replication_factor {
+		let pool_id: T::AssetId = Default::default();
+	}: _(RawOrigin::Root, pool_id.clone(), 3u32)
+	verify {
+		assert_eq!(<ReplicationFactor<T>>::get(pool_id), 3u32);
+	}
+}
+
+impl_benchmark_test_suite!(IrisSession, crate::mock::new_test_ext(), crate::mock::Test);
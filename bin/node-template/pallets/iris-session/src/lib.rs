@@ -16,22 +16,43 @@
 mod mock;
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::WeightInfo;
+
+use codec::{Encode, Decode};
 use frame_support::{
 	ensure,
 	pallet_prelude::*,
-	traits::{EstimateNextSessionRotation, Get, ValidatorSet, ValidatorSetWithIdentification},
+	traits::{
+		Currency, EstimateNextSessionRotation, Get, OnUnbalanced, ReservableCurrency, ValidatorSet,
+		ValidatorSetWithIdentification,
+	},
 };
 use log;
 pub use pallet::*;
-use sp_runtime::traits::{Convert, Zero};
-use sp_staking::offence::{Offence, OffenceError, ReportOffence};
+use scale_info::TypeInfo;
+use sp_runtime::traits::{Convert, Hash, Saturating, Zero};
+use sp_runtime::transaction_validity::{
+	InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity, ValidTransaction,
+};
+use sp_runtime::Perbill;
+use sp_staking::{
+	offence::{Kind, Offence, OffenceError, ReportOffence},
+	SessionIndex,
+};
 use sp_std::{
-	collections::btree_set::BTreeSet,
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
 	convert::TryInto,
 	str,
 	vec::Vec,
 	prelude::*
 };
+// `IpfsRequest`/`IpfsResponse` are the embedded-IPFS offchain request surface this node's
+// `sp_core` is built against; variants used here (`Identity`, `Connect`, `Disconnect`,
+// `AddBytes`, `CatBytes`, `InsertPin`, `RemovePin`, `LocalRefs`, `Peers`) must stay in sync
+// with that fork rather than being invented ad hoc.
 use sp_core::{
     offchain::{
         Duration, IpfsRequest, IpfsResponse, OpaqueMultiaddr, Timestamp, StorageKind,
@@ -39,20 +60,95 @@ use sp_core::{
     Bytes,
 };
 use frame_system::{
-	self as system, ensure_signed,
+	self as system, ensure_none, ensure_signed,
 	offchain::{
-		SendSignedTransaction,
+		SendUnsignedTransaction,
+		SignedPayload,
 		Signer,
 	}
 };
 use sp_io::offchain::timestamp;
-use sp_runtime::offchain::ipfs;
+use sp_runtime::offchain::{
+	ipfs,
+	storage_lock::{BlockAndTime, StorageLock},
+};
 use pallet_iris_assets::{
 	DataCommand,
 };
 
 pub const LOG_TARGET: &'static str = "runtime::iris-session";
 
+/// storage lock key guarding the offchain worker against overlapping runs
+pub const IPFS_LOCK_KEY: &[u8] = b"iris::ipfs-lock";
+/// number of blocks after which the offchain lock auto-expires if a worker dies mid-run
+pub const IPFS_LOCK_BLOCK_EXPIRATION: u32 = 3;
+/// wall-clock deadline (ms) for the offchain lock, in addition to the block deadline
+pub const IPFS_LOCK_TIMEOUT_MS: u64 = 10_000;
+
+/// balance type used for storage pool accounting, aliased from the underlying assets pallet
+pub type BalanceOf<T> = <T as pallet_assets::Config>::Balance;
+
+/// native currency balance type used for storage-pool bonding
+pub type BondBalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// imbalance type produced by slashing a validator's bond, handed to `T::OnSlash`
+pub type NegativeImbalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
+
+/// era points awarded per validator per call to `reward_validators`, mirroring the 20
+/// points-per-authored-block convention used by `pallet-staking`
+pub const POINTS_PER_BLOCK: u32 = 20;
+
+/// lets an external payout pallet drain this pallet's per-session reward point tally once it
+/// has used it to compute a payout, so points for a session aren't counted twice
+pub trait DrainSessionPoints<AccountId> {
+	fn drain_session_points(session: SessionIndex) -> BTreeMap<AccountId, u32>;
+}
+
+/// An offence raised against storage providers that fail a proof-of-pinning audit, i.e. whose
+/// offchain worker self-reported missing enough of its assigned CIDs, over `AuditWindow`
+/// sessions, to exceed `AuditThreshold`.
+///
+/// Mirrors the slow-clap pallet's offence shape: the slash fraction scales with how large a
+/// portion of the validator set offended, so an isolated fault is cheap while a
+/// network-wide one approaches a full slash.
+#[derive(RuntimeDebug)]
+pub struct IrisUnpinnedOffence<AccountId> {
+	/// the session index this offence was raised in
+	pub session_index: SessionIndex,
+	/// the size of the validator set at the time of the offence
+	pub validator_set_count: u32,
+	/// the offending storage providers, paired with their identification as required by
+	/// `ReportOffence`
+	pub offenders: Vec<(AccountId, AccountId)>,
+}
+
+impl<AccountId: Clone> Offence<(AccountId, AccountId)> for IrisUnpinnedOffence<AccountId> {
+	const ID: Kind = *b"iris:unpinned000";
+	type TimeSlot = SessionIndex;
+
+	fn offenders(&self) -> Vec<(AccountId, AccountId)> {
+		self.offenders.clone()
+	}
+
+	fn session_index(&self) -> SessionIndex {
+		self.session_index
+	}
+
+	fn validator_set_count(&self) -> u32 {
+		self.validator_set_count
+	}
+
+	fn time_slot(&self) -> Self::TimeSlot {
+		self.session_index
+	}
+
+	fn slash_fraction(&self, offenders_count: u32) -> Perbill {
+		Perbill::from_rational(offenders_count, self.validator_set_count.max(1))
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -67,7 +163,12 @@ pub mod pallet {
 	/// Configure the pallet by specifying the parameters and types on which it
 	/// depends.
 	#[pallet::config]
-	pub trait Config: frame_system::Config + pallet_session::Config + pallet_iris_assets::Config {
+	pub trait Config:
+		CreateSignedTransaction<Call<Self>>
+		+ frame_system::Config
+		+ pallet_session::Config
+		+ pallet_iris_assets::Config
+	{
 		/// The Event type.
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		/// the overarching call type
@@ -77,14 +178,122 @@ pub mod pallet {
 		/// Minimum number of validators to leave in the validator set during
 		/// auto removal.
 		type MinAuthorities: Get<u32>;
-		/// the authority id used for sending signed txs
+		/// the authority id used for signing offchain worker payloads
         type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+		/// priority given to unsigned transactions submitted by the offchain worker
+		type UnsignedPriority: Get<TransactionPriority>;
+		/// number of missing CIDs, accumulated over `AuditWindow` sessions, that a storage
+		/// provider's proof-of-pinning reports may reach before an offence is raised
+		type AuditThreshold: Get<u32>;
+		/// number of sessions over which missing-CID counts are accumulated before the
+		/// audit window resets
+		type AuditWindow: Get<u32>;
+		/// sink for proof-of-pinning offences raised by the audit subsystem
+		type ReportOffence: ReportOffence<
+			Self::AccountId,
+			(Self::AccountId, Self::AccountId),
+			IrisUnpinnedOffence<Self::AccountId>,
+		>;
+		/// native currency used to bond storage providers against a pool
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// amount reserved from a node's free balance when it joins a storage pool
+		type PoolBond: Get<BondBalanceOf<Self>>;
+		/// number of sessions a bond remains reserved after a provider requests to exit a
+		/// storage pool, before it can be unreserved
+		type UnbondingDelay: Get<u32>;
+		/// weight information for extrinsics in this pallet
+		type WeightInfo: WeightInfo;
+		/// amount reserved from a validator's free balance when it joins the validator set
+		type ValidatorBond: Get<BondBalanceOf<Self>>;
+		/// fraction of a validator's bond slashed for a "soft" offence kind (currently just
+		/// `IrisUnpinnedOffence`, i.e. unresponsiveness)
+		type MinorOffenceSlashFraction: Get<Perbill>;
+		/// fraction of a validator's bond slashed for any other ("hard"/equivocation-class)
+		/// offence kind
+		type MajorOffenceSlashFraction: Get<Perbill>;
+		/// destination for currency slashed from validator bonds
+		type OnSlash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+		/// number of sessions to wait, after an offender is marked for removal, before the
+		/// removal actually takes effect
+		type RemovalDeferWindow: Get<u32>;
+		/// number of sessions, counted from the session a validator was actually removed in,
+		/// before it is once again permitted to re-register
+		type OffenceCooldown: Get<u32>;
 	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
 	pub struct Pallet<T>(_);
 
+	/// signed payload submitted (unsigned, with a signature) by an offchain worker after
+	/// successfully re-adding fetched bytes to its local IPFS node
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct IpfsAddResultsPayload<T: Config> {
+		pub validator: T::AccountId,
+		pub admin: T::AccountId,
+		pub cid: Vec<u8>,
+		pub id: T::AssetId,
+		pub balance: BalanceOf<T>,
+		pub public: T::Public,
+	}
+
+	impl<T: Config> SignedPayload<T> for IpfsAddResultsPayload<T> {
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
+	}
+
+	/// signed payload submitted by an offchain worker once content has been fetched into
+	/// offchain local storage and is ready to be served over rpc
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct RpcReadyPayload<T: Config> {
+		pub validator: T::AccountId,
+		pub beneficiary: T::AccountId,
+		pub public: T::Public,
+	}
+
+	impl<T: Config> SignedPayload<T> for RpcReadyPayload<T> {
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
+	}
+
+	/// signed payload submitted by an offchain worker to publish its embedded IPFS node's
+	/// public key and known multiaddresses
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct IpfsIdentityPayload<T: Config> {
+		pub validator: T::AccountId,
+		pub public_key: Vec<u8>,
+		pub multiaddresses: Vec<OpaqueMultiaddr>,
+		pub public: T::Public,
+	}
+
+	impl<T: Config> SignedPayload<T> for IpfsIdentityPayload<T> {
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
+	}
+
+	/// signed payload submitted by an offchain worker reporting which of its assigned CIDs
+	/// are not currently pinned on its local IPFS node
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct PinningAuditPayload<T: Config> {
+		pub validator: T::AccountId,
+		pub missing: Vec<Vec<u8>>,
+		pub pinned: Vec<Vec<u8>>,
+		pub public: T::Public,
+	}
+
+	impl<T: Config> SignedPayload<T> for PinningAuditPayload<T> {
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
+	}
+
     /// map the ipfs public key to a list of multiaddresses
     /// this could be moved to the session pallet
     #[pallet::storage]
@@ -97,6 +306,19 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// map a validator's account to the multiaddresses its embedded IPFS node last self-reported
+    /// via `submit_ipfs_identity_unsigned`; used by `repair_replication` to dial an actual known
+    /// holder of an under-replicated CID instead of an arbitrary bootstrap node
+    #[pallet::storage]
+    #[pallet::getter(fn validator_addresses)]
+    pub type ValidatorAddresses<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Vec<OpaqueMultiaddr>,
+        ValueQuery,
+    >;
+
 	#[pallet::storage]
 	#[pallet::getter(fn validators)]
 	pub type Validators<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
@@ -109,6 +331,186 @@ pub mod pallet {
 	#[pallet::getter(fn validators_to_remove)]
 	pub type OfflineValidators<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
 
+	/// validators that called `go_offline` and are queued to be dropped from the active set at
+	/// the next `new_session`, while remaining in `ApprovedValidators` for re-entry via
+	/// `go_online`
+	#[pallet::storage]
+	#[pallet::getter(fn outgoing_validators)]
+	pub type OutgoingValidators<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
+	/// the CIDs each storage provider is currently expected to hold pinned
+	#[pallet::storage]
+	#[pallet::getter(fn assigned_cids)]
+	pub type AssignedCids<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BTreeSet<Vec<u8>>,
+		ValueQuery,
+	>;
+
+	/// running count, for the current audit window, of assigned CIDs each storage provider
+	/// has self-reported missing from its local IPFS node
+	#[pallet::storage]
+	#[pallet::getter(fn missing_cid_counts)]
+	pub type MissingCidCounts<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		u32,
+		ValueQuery,
+	>;
+
+	/// target number of providers that should hold a pin on content in a given storage pool
+	#[pallet::storage]
+	#[pallet::getter(fn replication_factor)]
+	pub type ReplicationFactor<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		u32,
+		ValueQuery,
+	>;
+
+	/// providers known, from proof-of-pinning reports, to currently hold a pin on a CID
+	#[pallet::storage]
+	#[pallet::getter(fn pin_reports)]
+	pub type PinReports<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		BTreeSet<T::AccountId>,
+		ValueQuery,
+	>;
+
+	/// CIDs registered to each storage pool, used to evaluate replication targets
+	#[pallet::storage]
+	#[pallet::getter(fn pool_cids)]
+	pub type PoolCids<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		BTreeSet<Vec<u8>>,
+		ValueQuery,
+	>;
+
+	/// admin and balance recorded for a CID the first time it is added, replayed into repair
+	/// jobs once the CID falls under its pool's replication factor
+	#[pallet::storage]
+	#[pallet::getter(fn cid_metadata)]
+	pub type CidMetadata<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		(T::AccountId, BalanceOf<T>),
+		OptionQuery,
+	>;
+
+	/// bond currently reserved from a storage provider against a given pool, taken on
+	/// `request_join_storage_pool` and released (minus any slashes) once unbonding completes.
+	/// Keyed as a double map on the provider first so slashing an offender (`slash_bonds`) only
+	/// has to scan the pools that one account is actually bonded in, not every bond on chain.
+	#[pallet::storage]
+	#[pallet::getter(fn pool_bonds)]
+	pub type PoolBonds<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AssetId,
+		BondBalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// pending unbonding requests, keyed by (provider, pool), recording the session index at
+	/// which the bond becomes free to unreserve
+	#[pallet::storage]
+	#[pallet::getter(fn unbonding_requests)]
+	pub type UnbondingRequests<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		(T::AccountId, T::AssetId),
+		SessionIndex,
+		OptionQuery,
+	>;
+
+	/// bond reserved from a validator's free balance while it is a member of the validator set,
+	/// drawn down by `ReportOffence` when it offends
+	#[pallet::storage]
+	#[pallet::getter(fn validator_bonds)]
+	pub type ValidatorBonds<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BondBalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// pending validator-bond unbonding requests, recording the session index at which a
+	/// removed validator's `ValidatorBonds` entry becomes free to unreserve. Mirrors
+	/// `UnbondingRequests`/`process_unbonding` for `PoolBonds`.
+	#[pallet::storage]
+	#[pallet::getter(fn validator_unbonding_requests)]
+	pub type ValidatorUnbondingRequests<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		SessionIndex,
+		OptionQuery,
+	>;
+
+	/// session at which a validator marked for removal is (or was) dropped from the active set;
+	/// kept after removal executes so `can_rejoin` can measure the `OffenceCooldown` elapsed
+	/// since, and cleared once the validator successfully re-registers
+	#[pallet::storage]
+	#[pallet::getter(fn removal_schedule)]
+	pub type RemovalSchedule<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		SessionIndex,
+		OptionQuery,
+	>;
+
+	/// per-session tally of reward points earned by each validator, for an external payout
+	/// pallet to drain (via `DrainSessionPoints`) and turn into a staking reward
+	#[pallet::storage]
+	#[pallet::getter(fn reward_points)]
+	pub type RewardPoints<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		SessionIndex,
+		BTreeMap<T::AccountId, u32>,
+		ValueQuery,
+	>;
+
+	/// cumulative amount already slashed from an offender's bond for a given report, keyed by
+	/// `(report id, offender)`; lets `report_offence` escalate a validator's slash as the
+	/// concurrent-offender ratio for its time slot grows, without re-slashing what was already
+	/// taken
+	#[pallet::storage]
+	#[pallet::getter(fn slot_slashed)]
+	pub type SlotSlashed<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		(T::Hash, T::AccountId),
+		BondBalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// offenders already recorded against a given `(offence kind, time slot)`, keyed by the hash
+	/// of that pair since `ReportOffence` is implemented generically over the offence type. Used
+	/// to make `report_offence` idempotent across repeated reports for the same misbehaviour.
+	#[pallet::storage]
+	#[pallet::getter(fn reported_offences)]
+	pub type ReportedOffences<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,
+		BTreeSet<T::AccountId>,
+		ValueQuery,
+	>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -120,6 +522,26 @@ pub mod pallet {
 		PublishedIdentity(T::AccountId),
 		/// A validator requested to join a storage pool
 		RequestJoinStoragePoolSuccess(T::AccountId, T::AssetId),
+		/// A storage provider self-reported CIDs missing from its local IPFS node
+		PinningAuditReported(T::AccountId, u32),
+		/// A storage provider was reported for a proof-of-pinning offence
+		PinningOffenceReported(T::AccountId),
+		/// The target replication factor for a storage pool was updated
+		ReplicationFactorSet(T::AssetId, u32),
+		/// A storage provider reserved a bond to join a storage pool
+		Bonded(T::AccountId, T::AssetId, BondBalanceOf<T>),
+		/// A storage provider's bond was unreserved after its unbonding delay elapsed
+		Unbonded(T::AccountId, T::AssetId, BondBalanceOf<T>),
+		/// A storage provider's bond was slashed following a proof-of-pinning offence
+		Slashed(T::AccountId, T::AssetId, BondBalanceOf<T>),
+		/// A validator requested to go offline and will be dropped at the next session
+		ValidatorWentOffline(T::AccountId),
+		/// A validator returned from self-requested offline status and rejoined the active set
+		ValidatorWentOnline(T::AccountId),
+		/// A validator's bond was slashed following a reported offence
+		ValidatorSlashed { validator: T::AccountId, amount: BondBalanceOf<T> },
+		/// A removed validator's bond was unreserved after its unbonding delay elapsed
+		ValidatorUnbonded(T::AccountId, BondBalanceOf<T>),
 	}
 
 	// Errors inform users that something went wrong.
@@ -143,6 +565,11 @@ pub mod pallet {
 		NoSuchOwnedContent,
 		/// the nodes balance is insufficient to complete this operation
 		InsufficientBalance,
+		/// the caller has no bond reserved for the given storage pool
+		NotBonded,
+		/// the validator was recently removed for an offence and its `OffenceCooldown` has not
+		/// yet elapsed
+		OffenceCooldownActive,
 	}
 
 	#[pallet::hooks]
@@ -165,6 +592,13 @@ pub mod pallet {
                     log::error!("IPFS: Encountered an error while obtaining metadata: {:?}", e);
                 }
             }
+
+            // every 5 blocks, self-report any assigned CIDs this node no longer has pinned
+            if block_number % 5u32.into() == 0u32.into() {
+                if let Err(e) = Self::audit_pinned_content() {
+                    log::error!("IPFS: Encountered an error during the proof-of-pinning audit: {:?}", e);
+                }
+            }
 		}
 	}
 
@@ -197,7 +631,7 @@ pub mod pallet {
 		/// The origin can be configured using the `AddRemoveOrigin` type in the
 		/// host runtime. Can also be set to sudo/root.
 		///
-		#[pallet::weight(0)]
+		#[pallet::weight(T::WeightInfo::add_validator(<Validators<T>>::get().len() as u32))]
 		pub fn add_validator(origin: OriginFor<T>, validator_id: T::AccountId) -> DispatchResult {
 			T::AddRemoveOrigin::ensure_origin(origin)?;
 
@@ -211,7 +645,7 @@ pub mod pallet {
 		///
 		/// The origin can be configured using the `AddRemoveOrigin` type in the
 		/// host runtime. Can also be set to sudo/root.
-		#[pallet::weight(0)]
+		#[pallet::weight(T::WeightInfo::remove_validator(<Validators<T>>::get().len() as u32))]
 		pub fn remove_validator(
 			origin: OriginFor<T>,
 			validator_id: T::AccountId,
@@ -227,7 +661,7 @@ pub mod pallet {
 		/// Add an approved validator again when it comes back online.
 		///
 		/// For this call, the dispatch origin must be the validator itself.
-		#[pallet::weight(0)]
+		#[pallet::weight(T::WeightInfo::add_validator_again(<Validators<T>>::get().len() as u32))]
 		pub fn add_validator_again(
 			origin: OriginFor<T>,
 			validator_id: T::AccountId,
@@ -243,7 +677,59 @@ pub mod pallet {
 			Ok(())
 		}
 
-		#[pallet::weight(0)]
+		/// Voluntarily step down as an active validator.
+		///
+		/// The caller must currently be an approved validator. Rather than being removed
+		/// immediately, it is queued in `OutgoingValidators` and dropped from the active set at
+		/// the next `new_session`, while remaining approved so `go_online` can bring it back.
+		#[pallet::weight(T::WeightInfo::go_offline())]
+		pub fn go_offline(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let approved_set: BTreeSet<_> = <ApprovedValidators<T>>::get().into_iter().collect();
+			ensure!(approved_set.contains(&who), Error::<T>::ValidatorNotApproved);
+
+			let outgoing_set: BTreeSet<_> = <OutgoingValidators<T>>::get().into_iter().collect();
+			ensure!(!outgoing_set.contains(&who), Error::<T>::Duplicate);
+
+			// account for validators already queued to leave at the next session, not just the
+			// caller, so several validators calling `go_offline` in the same session can't
+			// together drop the active set below `MinAuthorities` once drained all at once
+			let active_count = <Validators<T>>::get().len();
+			ensure!(
+				active_count.saturating_sub(outgoing_set.len() + 1) as u32 >= T::MinAuthorities::get(),
+				Error::<T>::TooLowValidatorCount
+			);
+
+			<OutgoingValidators<T>>::mutate(|v| v.push(who.clone()));
+			Self::deposit_event(Event::ValidatorWentOffline(who));
+
+			Ok(())
+		}
+
+		/// Rejoin the active validator set after a self-requested `go_offline`.
+		///
+		/// The caller must be an approved validator that is not already active. Takes effect
+		/// immediately, exactly like `add_validator_again`.
+		#[pallet::weight(T::WeightInfo::go_online())]
+		pub fn go_online(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let approved_set: BTreeSet<_> = <ApprovedValidators<T>>::get().into_iter().collect();
+			ensure!(approved_set.contains(&who), Error::<T>::ValidatorNotApproved);
+
+			let active_set: BTreeSet<_> = <Validators<T>>::get().into_iter().collect();
+			ensure!(!active_set.contains(&who), Error::<T>::Duplicate);
+			ensure!(Self::can_rejoin(&who), Error::<T>::OffenceCooldownActive);
+
+			<Validators<T>>::mutate(|v| v.push(who.clone()));
+			<OutgoingValidators<T>>::mutate(|v| v.retain(|x| x != &who));
+			Self::deposit_event(Event::ValidatorWentOnline(who));
+
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::request_join_storage_pool())]
 		pub fn request_join_storage_pool(
 			origin: OriginFor<T>,
 			pool_owner: <T::Lookup as StaticLookup>::Source,
@@ -251,15 +737,197 @@ pub mod pallet {
 		) -> DispatchResult {
 			// submit a request to join a storage pool in the next session
 			let who = ensure_signed(origin)?;
+
+			let bond = T::PoolBond::get();
+			ensure!(T::Currency::can_reserve(&who, bond), Error::<T>::InsufficientBalance);
+
 			let new_origin = system::RawOrigin::Signed(who.clone()).into();
 			<pallet_iris_assets::Pallet<T>>::try_add_candidate_storage_provider(
 				new_origin,
 				pool_id.clone(),
 			)?;
 
+			T::Currency::reserve(&who, bond)?;
+			<PoolBonds<T>>::insert(&who, &pool_id, bond);
+			Self::deposit_event(Event::Bonded(who.clone(), pool_id.clone(), bond));
+
 			Self::deposit_event(Event::RequestJoinStoragePoolSuccess(who.clone(), pool_id.clone()));
 			Ok(())
 		}
+
+		/// Request to leave a storage pool.
+		///
+		/// The caller's bond is not unreserved immediately; it remains locked for
+		/// `T::UnbondingDelay` sessions, after which `new_session` releases it automatically.
+		#[pallet::weight(T::WeightInfo::request_exit_storage_pool())]
+		pub fn request_exit_storage_pool(
+			origin: OriginFor<T>,
+			pool_id: T::AssetId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(<PoolBonds<T>>::contains_key(&who, &pool_id), Error::<T>::NotBonded);
+
+			let unlock_at = Self::session_index().saturating_add(T::UnbondingDelay::get());
+			<UnbondingRequests<T>>::insert((who, pool_id), unlock_at);
+
+			Ok(())
+		}
+
+		/// Set the target replication factor for a storage pool.
+		///
+		/// The origin can be configured using the `AddRemoveOrigin` type in the host runtime.
+		#[pallet::weight(T::WeightInfo::set_replication_factor())]
+		pub fn set_replication_factor(
+			origin: OriginFor<T>,
+			pool_id: T::AssetId,
+			factor: u32,
+		) -> DispatchResult {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			<ReplicationFactor<T>>::insert(pool_id.clone(), factor);
+			Self::deposit_event(Event::ReplicationFactorSet(pool_id, factor));
+			Ok(())
+		}
+
+		/// relay the result of an offchain worker's `AddBytes` request to `pallet-iris-assets`.
+		///
+		/// Submitted unsigned, carrying a payload signed by the reporting validator's
+		/// `AuthorityId` key; see `validate_unsigned` for the checks performed before this
+		/// enters the pool.
+		#[pallet::weight(T::WeightInfo::submit_ipfs_add_results_unsigned())]
+		pub fn submit_ipfs_add_results_unsigned(
+			origin: OriginFor<T>,
+			payload: IpfsAddResultsPayload<T>,
+			_signature: T::Signature,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			<pallet_iris_assets::Pallet<T>>::submit_ipfs_add_results(
+				system::RawOrigin::Signed(payload.validator.clone()).into(),
+				payload.admin.clone(),
+				payload.cid.clone(),
+				payload.id.clone(),
+				payload.balance.clone(),
+			)?;
+
+			// the reporting validator now holds this content and is expected to keep it pinned
+			<AssignedCids<T>>::mutate(&payload.validator, |cids| {
+				cids.insert(payload.cid.clone());
+			});
+			<PinReports<T>>::mutate(&payload.cid, |providers| {
+				providers.insert(payload.validator.clone());
+			});
+			<PoolCids<T>>::mutate(&payload.id, |cids| {
+				cids.insert(payload.cid.clone());
+			});
+			<CidMetadata<T>>::insert(payload.cid.clone(), (payload.admin.clone(), payload.balance.clone()));
+
+			Ok(())
+		}
+
+		/// relay notice that requested content is ready to be served over rpc.
+		///
+		/// Submitted unsigned, carrying a payload signed by the reporting validator's
+		/// `AuthorityId` key; see `validate_unsigned` for the checks performed before this
+		/// enters the pool.
+		#[pallet::weight(T::WeightInfo::submit_rpc_ready_unsigned())]
+		pub fn submit_rpc_ready_unsigned(
+			origin: OriginFor<T>,
+			payload: RpcReadyPayload<T>,
+			_signature: T::Signature,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			<pallet_iris_assets::Pallet<T>>::submit_rpc_ready(
+				system::RawOrigin::Signed(payload.validator.clone()).into(),
+				payload.beneficiary.clone(),
+			)?;
+
+			Ok(())
+		}
+
+		/// relay an offchain worker's embedded IPFS node identity to `pallet-iris-assets`.
+		///
+		/// Submitted unsigned, carrying a payload signed by the reporting validator's
+		/// `AuthorityId` key; see `validate_unsigned` for the checks performed before this
+		/// enters the pool.
+		#[pallet::weight(T::WeightInfo::submit_ipfs_identity_unsigned())]
+		pub fn submit_ipfs_identity_unsigned(
+			origin: OriginFor<T>,
+			payload: IpfsIdentityPayload<T>,
+			_signature: T::Signature,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			<pallet_iris_assets::Pallet<T>>::submit_ipfs_identity(
+				system::RawOrigin::Signed(payload.validator.clone()).into(),
+				payload.public_key.clone(),
+				payload.multiaddresses.clone(),
+			)?;
+
+			<ValidatorAddresses<T>>::insert(&payload.validator, payload.multiaddresses.clone());
+
+			Ok(())
+		}
+
+		/// relay a storage provider's self-reported proof-of-pinning audit.
+		///
+		/// Submitted unsigned, carrying a payload signed by the reporting validator's
+		/// `AuthorityId` key; accumulates missing-CID counts for the current audit window,
+		/// which `new_session` inspects to raise an [`IrisUnpinnedOffence`] once
+		/// `T::AuditThreshold` is exceeded.
+		#[pallet::weight(T::WeightInfo::submit_pinning_audit_unsigned())]
+		pub fn submit_pinning_audit_unsigned(
+			origin: OriginFor<T>,
+			payload: PinningAuditPayload<T>,
+			_signature: T::Signature,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			let missing_count = payload.missing.len() as u32;
+			<MissingCidCounts<T>>::mutate(&payload.validator, |count| {
+				*count = count.saturating_add(missing_count);
+			});
+
+			for cid in payload.pinned.iter() {
+				<PinReports<T>>::mutate(cid, |providers| {
+					providers.insert(payload.validator.clone());
+				});
+			}
+			for cid in payload.missing.iter() {
+				<PinReports<T>>::mutate(cid, |providers| {
+					providers.remove(&payload.validator);
+				});
+			}
+
+			Self::deposit_event(Event::PinningAuditReported(payload.validator.clone(), missing_count));
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		/// confirm the payload signature is valid and that the reporting account is a known
+		/// validator before admitting the unsigned relay calls into the transaction pool
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			match call {
+				Call::submit_ipfs_add_results_unsigned { payload, signature } => {
+					let discriminator = (&payload.cid, &payload.id).using_encoded(T::Hashing::hash);
+					Self::validate_signed_payload(payload, signature, &payload.validator, b"iris::submit_ipfs_add_results", discriminator)
+				},
+				Call::submit_rpc_ready_unsigned { payload, signature } =>
+					Self::validate_signed_payload(payload, signature, &payload.validator, b"iris::submit_rpc_ready", T::Hash::default()),
+				Call::submit_ipfs_identity_unsigned { payload, signature } =>
+					Self::validate_signed_payload(payload, signature, &payload.validator, b"iris::submit_ipfs_identity", T::Hash::default()),
+				Call::submit_pinning_audit_unsigned { payload, signature } => {
+					let discriminator = (&payload.missing, &payload.pinned).using_encoded(T::Hashing::hash);
+					Self::validate_signed_payload(payload, signature, &payload.validator, b"iris::submit_pinning_audit", discriminator)
+				},
+				_ => InvalidTransaction::Call.into(),
+			}
+		}
 	}
 }
 
@@ -267,6 +935,21 @@ impl<T: Config> Pallet<T> {
 	fn initialize_validators(validators: &[T::AccountId]) {
 		assert!(validators.len() > 1, "At least 2 validators should be initialized");
 		assert!(<Validators<T>>::get().is_empty(), "Validators are already initialized!");
+
+		// bond genesis validators the same way `do_add_validator` bonds later ones, so an
+		// offence raised against a genesis validator can still be slashed via `ValidatorBonds`
+		// instead of silently no-opping against a bond that was never reserved.
+		let bond = T::ValidatorBond::get();
+		for validator in validators {
+			assert!(
+				T::Currency::can_reserve(validator, bond),
+				"Genesis validator does not have enough free balance to reserve the validator bond"
+			);
+			T::Currency::reserve(validator, bond)
+				.expect("can_reserve was just checked above for this validator; qed");
+			<ValidatorBonds<T>>::insert(validator, bond);
+		}
+
 		<Validators<T>>::put(validators);
 		<ApprovedValidators<T>>::put(validators);
 	}
@@ -274,6 +957,23 @@ impl<T: Config> Pallet<T> {
 	fn do_add_validator(validator_id: T::AccountId) -> DispatchResult {
 		let validator_set: BTreeSet<_> = <Validators<T>>::get().into_iter().collect();
 		ensure!(!validator_set.contains(&validator_id), Error::<T>::Duplicate);
+		ensure!(Self::can_rejoin(&validator_id), Error::<T>::OffenceCooldownActive);
+
+		// only bond on first entry; a validator re-added via `add_validator_again` after going
+		// offline keeps its existing bond rather than reserving a second one
+		if !<ValidatorBonds<T>>::contains_key(&validator_id) {
+			let bond = T::ValidatorBond::get();
+			ensure!(T::Currency::can_reserve(&validator_id, bond), Error::<T>::InsufficientBalance);
+			T::Currency::reserve(&validator_id, bond)?;
+			<ValidatorBonds<T>>::insert(&validator_id, bond);
+		}
+
+		// past its cooldown (if any); clear the stale schedule so a future offence starts fresh
+		<RemovalSchedule<T>>::remove(&validator_id);
+		// re-joining before a prior removal's unbonding delay elapsed keeps the existing bond
+		// in place rather than having it unreserved out from under the now-active validator
+		<ValidatorUnbondingRequests<T>>::remove(&validator_id);
+
 		<Validators<T>>::mutate(|v| v.push(validator_id.clone()));
 
 		Self::deposit_event(Event::ValidatorAdditionInitiated(validator_id.clone()));
@@ -296,6 +996,14 @@ impl<T: Config> Pallet<T> {
 
 		<Validators<T>>::put(validators);
 
+		// schedule the validator's bond to be unreserved once `UnbondingDelay` sessions have
+		// passed, mirroring `PoolBonds`' `UnbondingRequests`/`process_unbonding` path, rather
+		// than leaving the reserve permanently locked with no way to reclaim it
+		if <ValidatorBonds<T>>::contains_key(&validator_id) {
+			let unlock_at = Self::session_index().saturating_add(T::UnbondingDelay::get());
+			<ValidatorUnbondingRequests<T>>::insert(&validator_id, unlock_at);
+		}
+
 		Self::deposit_event(Event::ValidatorRemovalInitiated(validator_id.clone()));
 		log::debug!(target: LOG_TARGET, "Validator removal initiated.");
 
@@ -321,29 +1029,323 @@ impl<T: Config> Pallet<T> {
 	}
 
 	// Adds offline validators to a local cache for removal at new session.
+	// Schedules `validator_id` for removal at the end of the session `T::RemovalDeferWindow`
+	// sessions from now, rather than acting mid-session, so a session's validator set (and its
+	// keys) don't churn outside of the normal session-rotation boundary. Re-reporting an
+	// offender already scheduled leaves its original schedule untouched.
 	fn mark_for_removal(validator_id: T::AccountId) {
+		if !<RemovalSchedule<T>>::contains_key(&validator_id) {
+			let scheduled_at = Self::session_index().saturating_add(T::RemovalDeferWindow::get());
+			<RemovalSchedule<T>>::insert(&validator_id, scheduled_at);
+		}
 		<OfflineValidators<T>>::mutate(|v| v.push(validator_id));
-		log::debug!(target: LOG_TARGET, "Offline validator marked for auto removal.");
+		log::debug!(target: LOG_TARGET, "Offline validator marked for deferred removal.");
 	}
 
-	// Removes offline validators from the validator set and clears the offline
-	// cache. It is called in the session change hook and removes the validators
-	// who were reported offline during the session that is ending. We do not
-	// check for `MinAuthorities` here, because the offline validators will not
-	// produce blocks and will have the same overall effect on the runtime.
+	// Removes validators whose deferred removal schedule has come due from the active set. It
+	// is called in the session change hook; validators whose `RemovalDeferWindow` has not yet
+	// elapsed are kept pending and re-checked at the next session. We do not check for
+	// `MinAuthorities` here, because the offline validators will not produce blocks and will
+	// have the same overall effect on the runtime.
 	fn remove_offline_validators() {
-		let validators_to_remove: BTreeSet<_> = <OfflineValidators<T>>::get().into_iter().collect();
+		let current_session = Self::session_index();
+		let pending = <OfflineValidators<T>>::get();
+
+		let mut due = BTreeSet::new();
+		let mut still_pending = Vec::new();
+		for validator in pending.into_iter() {
+			let scheduled_at = <RemovalSchedule<T>>::get(&validator).unwrap_or(current_session);
+			if current_session >= scheduled_at {
+				due.insert(validator);
+			} else {
+				still_pending.push(validator);
+			}
+		}
 
 		// Delete from active validator set.
-		<Validators<T>>::mutate(|vs| vs.retain(|v| !validators_to_remove.contains(v)));
+		<Validators<T>>::mutate(|vs| vs.retain(|v| !due.contains(v)));
+		log::debug!(
+			target: LOG_TARGET,
+			"Removed {:?} offline validators; {:?} still pending their deferred removal.",
+			due.len(),
+			still_pending.len(),
+		);
+
+		<OfflineValidators<T>>::put(still_pending);
+	}
+
+	/// whether `validator` is currently free to re-register, i.e. either never removed for an
+	/// offence or far enough past its `OffenceCooldown` since the session it was removed in
+	pub fn can_rejoin(validator: &T::AccountId) -> bool {
+		match <RemovalSchedule<T>>::get(validator) {
+			Some(removed_at) =>
+				Self::session_index().saturating_sub(removed_at) >= T::OffenceCooldown::get(),
+			None => true,
+		}
+	}
+
+	// Drops validators that self-requested `go_offline` from the active set and clears the
+	// outgoing queue. Unlike `remove_offline_validators`, these validators remain in
+	// `ApprovedValidators` so `go_online` can bring them straight back.
+	fn drain_outgoing_validators() {
+		let outgoing: BTreeSet<_> = <OutgoingValidators<T>>::get().into_iter().collect();
+		if outgoing.is_empty() {
+			return;
+		}
+
+		<Validators<T>>::mutate(|vs| vs.retain(|v| !outgoing.contains(v)));
 		log::debug!(
 			target: LOG_TARGET,
-			"Initiated removal of {:?} offline validators.",
-			validators_to_remove.len()
+			"Dropped {:?} validator(s) that requested to go offline.",
+			outgoing.len()
 		);
 
-		// Clear the offline validator list to avoid repeated deletion.
-		<OfflineValidators<T>>::put(Vec::<T::AccountId>::new());
+		<OutgoingValidators<T>>::put(Vec::<T::AccountId>::new());
+	}
+
+	// Checks accumulated proof-of-pinning audit failures against `AuditThreshold` and raises
+	// an `IrisUnpinnedOffence` for any validator that has exceeded it, in addition to marking
+	// them for removal. Resets the per-window counters once every `AuditWindow` sessions.
+	fn audit_validators(session_index: SessionIndex) {
+		let validators = Self::validators();
+		let mut offenders = Vec::new();
+
+		for validator in validators.iter() {
+			if Self::missing_cid_counts(validator) > T::AuditThreshold::get() {
+				offenders.push((validator.clone(), validator.clone()));
+				// deferred removal is scheduled below by `T::ReportOffence::report_offence`'s own
+				// `mark_for_removal` call for each newly-reported offender; scheduling it here too
+				// would double-push the same validator into `OfflineValidators` every session it's
+				// flagged
+				Self::deposit_event(Event::PinningOffenceReported(validator.clone()));
+			}
+		}
+
+		if !offenders.is_empty() {
+			let offence = IrisUnpinnedOffence {
+				session_index,
+				validator_set_count: validators.len() as u32,
+				offenders: offenders.clone(),
+			};
+			let fraction = offence.slash_fraction(offenders.len() as u32);
+			// a proof-of-pinning offence slashes two independent bonds on the offending account:
+			// `slash_bonds` here takes its storage-pool collateral (the stake backing the content
+			// it failed to keep pinned), while `T::ReportOffence::report_offence` below separately
+			// drives `slash_validator_bond` against its validator collateral (the stake backing
+			// its place in the active set). These are deliberately two different slashes of two
+			// different bonds for the one offence, not a double-count of the same funds.
+			for (offender, _) in offenders.iter() {
+				Self::slash_bonds(offender, fraction);
+			}
+
+			if let Err(e) = T::ReportOffence::report_offence(Vec::new(), offence) {
+				log::warn!(
+					target: LOG_TARGET,
+					"failed to report a proof-of-pinning offence: {:?}",
+					e,
+				);
+			}
+		}
+
+		if T::AuditWindow::get() != 0 && session_index % T::AuditWindow::get() == 0 {
+			let _ = <MissingCidCounts<T>>::clear(u32::MAX, None);
+		}
+	}
+
+	// Slashes `fraction` of every pool bond held by `offender`. Iterates only `offender`'s own
+	// bonds via the `PoolBonds` double map's account prefix, rather than every bond on chain, so
+	// this stays cheap to run unconditionally from `new_session` as the storage-provider base
+	// grows.
+	fn slash_bonds(offender: &T::AccountId, fraction: Perbill) {
+		let bonds: Vec<_> = <PoolBonds<T>>::iter_prefix(offender).collect();
+		for (pool_id, bonded) in bonds {
+			let slash_amount = fraction * bonded;
+			if slash_amount.is_zero() {
+				continue;
+			}
+
+			let (imbalance, _) = T::Currency::slash_reserved(offender, slash_amount);
+			T::OnSlash::on_unbalanced(imbalance);
+			let remaining = bonded.saturating_sub(slash_amount);
+			<PoolBonds<T>>::insert(offender, &pool_id, remaining);
+			Self::deposit_event(Event::Slashed(offender.clone(), pool_id, slash_amount));
+		}
+	}
+
+	// Severity-based slash fraction for a reported offence kind: a small fraction for the
+	// "soft" unresponsiveness offence this pallet itself raises, a larger fraction for any other
+	// (e.g. equivocation-class) offence kind fed in by an external reporter.
+	fn severity_slash_fraction(kind: Kind) -> Perbill {
+		if kind == IrisUnpinnedOffence::<T::AccountId>::ID {
+			T::MinorOffenceSlashFraction::get()
+		} else {
+			T::MajorOffenceSlashFraction::get()
+		}
+	}
+
+	// Slash fraction driven by how many validators have offended within the same time slot, as
+	// `o` grows past a tenth of the validator set: `max(0, 3*(o-(n/10+1)))/n`, saturating to
+	// `[0,1]`. An isolated fault lands at zero; a coordinated, near-majority fault approaches a
+	// full slash.
+	fn concurrency_slash_fraction(offenders_in_slot: u32, validator_count: u32) -> Perbill {
+		if validator_count == 0 {
+			return Perbill::zero();
+		}
+
+		let threshold = validator_count / 10 + 1;
+		let over_threshold = offenders_in_slot.saturating_sub(threshold);
+		Perbill::from_rational(over_threshold.saturating_mul(3), validator_count)
+	}
+
+	// Brings `who`'s slash for `report_id` up to `fraction` of its bond (as it stood before any
+	// slashing occurred for this report), slashing only the incremental amount above what has
+	// already been taken. Called for every known offender in the slot on each report, so a
+	// growing concurrency fraction escalates validators slashed by an earlier, smaller report.
+	fn slash_validator_bond(report_id: &T::Hash, who: &T::AccountId, fraction: Perbill) {
+		let remaining_bond = <ValidatorBonds<T>>::get(who);
+		let already_slashed = <SlotSlashed<T>>::get((report_id, who));
+		let original_bond = remaining_bond.saturating_add(already_slashed);
+		if original_bond.is_zero() {
+			return;
+		}
+
+		let target_total = fraction * original_bond;
+		if target_total <= already_slashed {
+			return;
+		}
+
+		let incremental = target_total.saturating_sub(already_slashed).min(remaining_bond);
+		if incremental.is_zero() {
+			return;
+		}
+
+		let (imbalance, _) = T::Currency::slash_reserved(who, incremental);
+		<ValidatorBonds<T>>::insert(who, remaining_bond.saturating_sub(incremental));
+		<SlotSlashed<T>>::insert((report_id, who), already_slashed.saturating_add(incremental));
+		T::OnSlash::on_unbalanced(imbalance);
+		Self::deposit_event(Event::ValidatorSlashed { validator: who.clone(), amount: incremental });
+	}
+
+	// Derives the `ReportedOffences` key for a given offence kind and time slot. Hashing the
+	// pair, rather than storing it directly, lets the map be keyed uniformly even though
+	// `ReportOffence` is implemented generically over any `O: Offence<...>`.
+	fn offence_report_id<O: Offence<(T::AccountId, T::AccountId)>>(time_slot: &O::TimeSlot) -> T::Hash {
+		(O::ID, time_slot.encode()).using_encoded(T::Hashing::hash)
+	}
+
+	// Unreserves any bonds whose unbonding delay has elapsed as of `session_index`.
+	fn process_unbonding(session_index: SessionIndex) {
+		let due: Vec<_> = <UnbondingRequests<T>>::iter()
+			.filter(|(_, unlock_at)| *unlock_at <= session_index)
+			.map(|(key, _)| key)
+			.collect();
+
+		for key in due {
+			let (who, pool_id) = key.clone();
+			let bonded = <PoolBonds<T>>::take(&who, &pool_id);
+			T::Currency::unreserve(&who, bonded);
+			<UnbondingRequests<T>>::remove(&key);
+			Self::deposit_event(Event::Unbonded(who, pool_id, bonded));
+		}
+	}
+
+	// Unreserves any validator bonds scheduled by `do_remove_validator` whose unbonding delay
+	// has elapsed as of `session_index`. Mirrors `process_unbonding` for `PoolBonds`.
+	fn process_validator_unbonding(session_index: SessionIndex) {
+		let due: Vec<_> = <ValidatorUnbondingRequests<T>>::iter()
+			.filter(|(_, unlock_at)| *unlock_at <= session_index)
+			.map(|(who, _)| who)
+			.collect();
+
+		for who in due {
+			let bonded = <ValidatorBonds<T>>::take(&who);
+			T::Currency::unreserve(&who, bonded);
+			<ValidatorUnbondingRequests<T>>::remove(&who);
+			Self::deposit_event(Event::ValidatorUnbonded(who, bonded));
+		}
+	}
+
+	// For every CID registered to a pool, compares the number of known holders (from
+	// `PinReports`) against the pool's `ReplicationFactor` and enqueues an `AddBytes` repair
+	// job for any CID that falls short. The repair job is picked up like any other data
+	// queue entry; whichever approved provider's offchain worker processes it next pins the
+	// content and becomes a new holder via the existing audit/assignment bookkeeping.
+	//
+	// The fetch address is drawn from an account in `ApprovedValidators` that `PinReports`
+	// already lists as an actual holder of the CID (via its self-reported `ValidatorAddresses`),
+	// not an arbitrary bootstrap node, so the repair job has a real chance of succeeding rather
+	// than silently failing `CatBytes` against a peer that never had the content.
+	fn repair_replication() {
+		let approved: BTreeSet<_> = <ApprovedValidators<T>>::get().into_iter().collect();
+
+		for (pool_id, cids) in <PoolCids<T>>::iter() {
+			let target = Self::replication_factor(&pool_id);
+			if target == 0 {
+				continue;
+			}
+
+			for cid in cids.iter() {
+				let holders = Self::pin_reports(cid);
+				if (holders.len() as u32) >= target {
+					continue;
+				}
+
+				let (admin, balance) = match Self::cid_metadata(cid) {
+					Some(metadata) => metadata,
+					None => continue,
+				};
+				let addr = match holders.iter()
+					.filter(|holder| approved.contains(*holder))
+					.find_map(|holder| Self::validator_addresses(holder).into_iter().next())
+				{
+					Some(addr) => addr,
+					None => {
+						log::debug!(
+							target: LOG_TARGET,
+							"pool {:?}: no known address for an approved holder of an under-replicated Cid; skipping repair this session",
+							pool_id,
+						);
+						continue;
+					},
+				};
+
+				log::info!(
+					target: LOG_TARGET,
+					"pool {:?}: Cid is under-replicated ({} of {} target holders); enqueuing a repair job",
+					pool_id,
+					holders.len(),
+					target,
+				);
+				<pallet_iris_assets::Pallet<T>>::enqueue_data_command(DataCommand::AddBytes(
+					addr,
+					cid.clone(),
+					admin,
+					Vec::new(),
+					pool_id.clone(),
+					balance,
+				));
+			}
+		}
+	}
+
+	/// Credit `POINTS_PER_BLOCK` reward points, for the given session, to each validator in
+	/// `validators`. Intended to be called by whatever surfaces block authorship or
+	/// responsiveness for this chain (e.g. an `im-online`-style hook), keyed by
+	/// `Self::session_index()`.
+	pub fn reward_validators(session: SessionIndex, validators: &[T::AccountId]) {
+		<RewardPoints<T>>::mutate(session, |points| {
+			for validator in validators {
+				*points.entry(validator.clone()).or_insert(0) += POINTS_PER_BLOCK;
+			}
+		});
+	}
+
+	/// total reward points currently accumulated for `validator`, across every session not yet
+	/// drained by `DrainSessionPoints`
+	pub fn points_of(validator: &T::AccountId) -> u32 {
+		<RewardPoints<T>>::iter_values()
+			.map(|points| *points.get(validator).unwrap_or(&0))
+			.fold(0u32, |acc, p| acc.saturating_add(p))
 	}
 
 	/// implementation for RPC runtime aPI to retrieve bytes from the node's local storage
@@ -366,6 +1368,37 @@ impl<T: Config> Pallet<T> {
         }
     }
 	
+	/// verify a signed payload's signature and that it was signed by a known validator,
+	/// used by `validate_unsigned` to admit the offchain worker's unsigned relay calls.
+	///
+	/// `discriminator` is mixed into the pool's `provides` tag alongside `provides_tag` and
+	/// `validator` so that a validator reporting on several distinct jobs (e.g. two different
+	/// cids) within the same longevity window doesn't have its later reports rejected by the
+	/// pool as duplicates of the first.
+	fn validate_signed_payload<P: SignedPayload<T>>(
+		payload: &P,
+		signature: &T::Signature,
+		validator: &T::AccountId,
+		provides_tag: &'static [u8],
+		discriminator: T::Hash,
+	) -> TransactionValidity {
+		if !<Validators<T>>::get().contains(validator) {
+			return InvalidTransaction::BadSigner.into();
+		}
+
+		let signature_valid = SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone());
+		if !signature_valid {
+			return InvalidTransaction::BadProof.into();
+		}
+
+		ValidTransaction::with_tag_prefix("IrisOffchainWorker")
+			.priority(T::UnsignedPriority::get())
+			.and_provides((provides_tag, validator, discriminator))
+			.longevity(5)
+			.propagate(true)
+			.build()
+	}
+
 	 /// send a request to the local IPFS node; can only be called be an off-chain worker
 	 fn ipfs_request(
         req: IpfsRequest,
@@ -388,13 +1421,30 @@ impl<T: Config> Pallet<T> {
 	
 	/// manage connection to the iris ipfs swarm
     ///
-    /// If the node is already a bootstrap node, do nothing. Otherwise submits a signed tx 
-    /// containing the public key and multiaddresses of the embedded ipfs node.
+    /// If the node is already a bootstrap node, do nothing. Otherwise submits an unsigned tx,
+    /// carrying a payload signed by this validator's `AuthorityId` key, containing the public
+    /// key and multiaddresses of the embedded ipfs node.
     /// 
     /// Returns an error if communication with the embedded IPFS fails
     fn connection_housekeeping() -> Result<(), Error<T>> {
+        let mut lock = StorageLock::<BlockAndTime<frame_system::Pallet<T>>>::with_block_and_time_deadline(
+            IPFS_LOCK_KEY,
+            IPFS_LOCK_BLOCK_EXPIRATION,
+            Duration::from_millis(IPFS_LOCK_TIMEOUT_MS),
+        );
+        let _guard = match lock.try_lock() {
+            Ok(guard) => guard,
+            Err(_) => {
+                log::debug!(
+                    target: LOG_TARGET,
+                    "IPFS: another offchain worker instance holds the ipfs lock; skipping connection housekeeping this block.",
+                );
+                return Ok(());
+            },
+        };
+
         let deadline = Some(timestamp().add(Duration::from_millis(5_000)));
-        
+
         let (public_key, addrs) = 
 			if let IpfsResponse::Identity(public_key, addrs) = 
 				Self::ipfs_request(IpfsRequest::Identity, deadline)? {
@@ -422,27 +1472,25 @@ impl<T: Config> Pallet<T> {
                     }
                 }
             }
-            // let signer = Signer::<T, <T as pallet::Config>::AuthorityId>::all_accounts();
-            // if !signer.can_sign() {
-            //     log::error!(
-            //         "No local accounts available. Consider adding one via `author_insertKey` RPC.",
-            //     );
-            // }
-             
-            // let results = signer.send_signed_transaction(|_account| { 
-            //     pallet_iris_assets::Call::submit_ipfs_identity {
-            //         public_key: public_key.clone(),
-            //         multiaddresses: addrs.clone(),
-            //     }
-            // });
-    
-            // for (_, res) in &results {
-            //     match res {
-            //         Ok(()) => log::info!("Submitted ipfs identity results"),
-            //         Err(e) => log::error!("Failed to submit transaction: {:?}",  e),
-            //     }
-            // }
-
+            let signer = Signer::<T, <T as pallet::Config>::AuthorityId>::any_account();
+            if let Some((_, res)) = signer.send_unsigned_transaction(
+                |account| IpfsIdentityPayload {
+                    validator: account.id.clone(),
+                    public_key: public_key.clone(),
+                    multiaddresses: addrs.clone(),
+                    public: account.public.clone(),
+                },
+                |payload, signature| Call::submit_ipfs_identity_unsigned { payload, signature },
+            ) {
+                match res {
+                    Ok(()) => log::info!("Submitted ipfs identity results"),
+                    Err(e) => log::error!("Failed to submit transaction: {:?}", e),
+                }
+            } else {
+                log::error!(
+                    "No local accounts available. Consider adding one via `author_insertKey` RPC.",
+                );
+            }
         }
         Ok(())
 
@@ -450,6 +1498,22 @@ impl<T: Config> Pallet<T> {
 
 	/// process any requests in the DataQueue
     fn handle_data_requests() -> Result<(), Error<T>> {
+        let mut lock = StorageLock::<BlockAndTime<frame_system::Pallet<T>>>::with_block_and_time_deadline(
+            IPFS_LOCK_KEY,
+            IPFS_LOCK_BLOCK_EXPIRATION,
+            Duration::from_millis(IPFS_LOCK_TIMEOUT_MS),
+        );
+        let _guard = match lock.try_lock() {
+            Ok(guard) => guard,
+            Err(_) => {
+                log::debug!(
+                    target: LOG_TARGET,
+                    "IPFS: another offchain worker instance holds the ipfs lock; skipping the data queue this block.",
+                );
+                return Ok(());
+            },
+        };
+
         let data_queue = <pallet_iris_assets::Pallet<T>>::data_queue();
         let len = data_queue.len();
         if len != 0 {
@@ -479,32 +1543,34 @@ impl<T: Config> Pallet<T> {
                                         "IPFS: added data with Cid {}",
                                         str::from_utf8(&new_cid).expect("our own IPFS node can be trusted here; qed")
                                     );
-                                    let signer = Signer::<T, <T as pallet::Config>::AuthorityId>::all_accounts();
-                                    if !signer.can_sign() {
-                                        log::error!(
-                                            "No local accounts available. Consider adding one via `author_insertKey` RPC.",
-                                        );
+
+                                    match Self::ipfs_request(IpfsRequest::InsertPin(new_cid.clone()), deadline) {
+                                        Ok(IpfsResponse::Success) =>
+                                            log::info!("IPFS: pinned Cid {}", str::from_utf8(&new_cid).unwrap_or_default()),
+                                        Ok(_) => unreachable!("only Success can be a response for that request type."),
+                                        Err(e) => log::error!("IPFS: pin error: {:?}", e),
                                     }
-                                    let results = signer.send_signed_transaction(|_account| { 
-										// Ca::submit_ipfs_add_results{
-                                        //     admin: admin.clone(),
-                                        //     cid: new_cid.clone(),
-                                        //     id: id.clone(),
-                                        //     balance: balance.clone(),
-                                        // }
-                                        pallet_iris_assets::Call::submit_ipfs_add_results{
+
+                                    let signer = Signer::<T, <T as pallet::Config>::AuthorityId>::any_account();
+                                    if let Some((_, res)) = signer.send_unsigned_transaction(
+                                        |account| IpfsAddResultsPayload {
+                                            validator: account.id.clone(),
                                             admin: admin.clone(),
                                             cid: new_cid.clone(),
                                             id: id.clone(),
                                             balance: balance.clone(),
-                                        }
-                                     });
-                            
-                                    for (_, res) in &results {
+                                            public: account.public.clone(),
+                                        },
+                                        |payload, signature| Call::submit_ipfs_add_results_unsigned { payload, signature },
+                                    ) {
                                         match res {
                                             Ok(()) => log::info!("Submitted ipfs results"),
-                                            Err(e) => log::error!("Failed to submit transaction: {:?}",  e),
+                                            Err(e) => log::error!("Failed to submit transaction: {:?}", e),
                                         }
+                                    } else {
+                                        log::error!(
+                                            "No local accounts available. Consider adding one via `author_insertKey` RPC.",
+                                        );
                                     }
                                 },
                                 Ok(_) => unreachable!("only AddBytes can be a response for that request type."),
@@ -532,23 +1598,23 @@ impl<T: Config> Pallet<T> {
 									&cid,
 									&data,
 								);
-								let signer = Signer::<T, <T as pallet::Config>::AuthorityId>::all_accounts();
-								if !signer.can_sign() {
-									log::error!(
-										"No local accounts available. Consider adding one via `author_insertKey` RPC.",
-									);
-								}
-								let results = signer.send_signed_transaction(|_account| { 
-									pallet_iris_assets::Call::submit_rpc_ready {
+								let signer = Signer::<T, <T as pallet::Config>::AuthorityId>::any_account();
+								if let Some((_, res)) = signer.send_unsigned_transaction(
+									|account| RpcReadyPayload {
+										validator: account.id.clone(),
 										beneficiary: recipient.clone(),
-									}
-								});
-						
-								for (_, res) in &results {
+										public: account.public.clone(),
+									},
+									|payload, signature| Call::submit_rpc_ready_unsigned { payload, signature },
+								) {
 									match res {
 										Ok(()) => log::info!("Submitted ipfs results"),
-										Err(e) => log::error!("Failed to submit transaction: {:?}",  e),
+										Err(e) => log::error!("Failed to submit transaction: {:?}", e),
 									}
+								} else {
+									log::error!(
+										"No local accounts available. Consider adding one via `author_insertKey` RPC.",
+									);
 								}
 							},
 							Ok(_) => unreachable!("only CatBytes can be a response for that request type."),
@@ -582,6 +1648,55 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// self-report, to the proof-of-pinning audit, which of this node's assigned CIDs are no
+    /// longer pinned on its local IPFS node; also unpins anything held locally that this node
+    /// is no longer assigned, so storage no longer backed by a bond/pool doesn't linger forever.
+    fn audit_pinned_content() -> Result<(), Error<T>> {
+        let deadline = Some(timestamp().add(Duration::from_millis(5_000)));
+        let signer = Signer::<T, <T as pallet::Config>::AuthorityId>::any_account();
+
+        let outcome = signer.send_unsigned_transaction(
+            |account| {
+                let assigned = Self::assigned_cids(&account.id);
+                let pinned: BTreeSet<Vec<u8>> = match Self::ipfs_request(IpfsRequest::LocalRefs, deadline) {
+                    Ok(IpfsResponse::LocalRefs(cids)) => cids.into_iter().collect(),
+                    _ => BTreeSet::new(),
+                };
+                let missing: Vec<Vec<u8>> =
+                    assigned.iter().filter(|cid| !pinned.contains(*cid)).cloned().collect();
+                let still_pinned: Vec<Vec<u8>> =
+                    assigned.iter().filter(|cid| pinned.contains(*cid)).cloned().collect();
+
+                for cid in pinned.iter().filter(|cid| !assigned.contains(*cid)) {
+                    match Self::ipfs_request(IpfsRequest::RemovePin(cid.clone()), deadline) {
+                        Ok(IpfsResponse::Success) =>
+                            log::info!("IPFS: unpinned stale Cid {}", str::from_utf8(cid).unwrap_or_default()),
+                        Ok(_) => unreachable!("only Success can be a response for that request type."),
+                        Err(e) => log::error!("IPFS: unpin error: {:?}", e),
+                    }
+                }
+
+                PinningAuditPayload {
+                    validator: account.id.clone(),
+                    missing,
+                    pinned: still_pinned,
+                    public: account.public.clone(),
+                }
+            },
+            |payload, signature| Call::submit_pinning_audit_unsigned { payload, signature },
+        );
+
+        match outcome {
+            Some((_, Ok(()))) => log::info!("Submitted proof-of-pinning audit report"),
+            Some((_, Err(e))) => log::error!("Failed to submit transaction: {:?}", e),
+            None => log::error!(
+                "No local accounts available. Consider adding one via `author_insertKey` RPC.",
+            ),
+        }
+
+        Ok(())
+    }
+
 }
 
 // Provides the new set of validators to the session module when session is
@@ -593,9 +1708,13 @@ impl<T: Config> pallet_session::SessionManager<T::AccountId> for Pallet<T> {
 		// Remove any offline validators. This will only work when the runtime
 		// also has the im-online pallet.
 		Self::remove_offline_validators();
+		Self::drain_outgoing_validators();
 		log::debug!(target: LOG_TARGET, "New session called; updated validator set provided.");
 
-		// TODO: Need to verify that storage providers have data pinned...
+		Self::audit_validators(new_index);
+		Self::repair_replication();
+		Self::process_unbonding(new_index);
+		Self::process_validator_unbonding(new_index);
 
 		Some(Self::validators())
 	}
@@ -654,14 +1773,45 @@ impl<T: Config> ValidatorSetWithIdentification<T::AccountId> for Pallet<T> {
 	type IdentificationOf = ValidatorOf<T>;
 }
 
+impl<T: Config> DrainSessionPoints<T::AccountId> for Pallet<T> {
+	fn drain_session_points(session: SessionIndex) -> BTreeMap<T::AccountId, u32> {
+		<RewardPoints<T>>::take(session)
+	}
+}
+
 // Offence reporting and unresponsiveness management.
 impl<T: Config, O: Offence<(T::AccountId, T::AccountId)>>
 	ReportOffence<T::AccountId, (T::AccountId, T::AccountId), O> for Pallet<T>
 {
 	fn report_offence(_reporters: Vec<T::AccountId>, offence: O) -> Result<(), OffenceError> {
-		let offenders = offence.offenders();
+		let time_slot = offence.time_slot();
+		let report_id = Self::offence_report_id::<O>(&time_slot);
+
+		let mut known = <ReportedOffences<T>>::get(&report_id);
+		let mut new_offenders = Vec::new();
+		for (who, id) in offence.offenders().into_iter() {
+			if known.insert(who.clone()) {
+				new_offenders.push((who, id));
+			}
+		}
+
+		if new_offenders.is_empty() {
+			return Err(OffenceError::DuplicateReport);
+		}
+
+		<ReportedOffences<T>>::insert(report_id, known.clone());
+
+		let validator_count = Self::validators().len() as u32;
+		let fraction = Self::severity_slash_fraction(O::ID)
+			.saturating_add(Self::concurrency_slash_fraction(known.len() as u32, validator_count));
+
+		// escalate every offender known for this slot, not just the newly reported ones, since
+		// the concurrency fraction just computed may be higher than when they were first slashed
+		for who in known.iter() {
+			Self::slash_validator_bond(&report_id, who, fraction);
+		}
 
-		for (v, _) in offenders.into_iter() {
+		for (v, _) in new_offenders.into_iter() {
 			Self::mark_for_removal(v);
 		}
 
@@ -669,9 +1819,11 @@ impl<T: Config, O: Offence<(T::AccountId, T::AccountId)>>
 	}
 
 	fn is_known_offence(
-		_offenders: &[(T::AccountId, T::AccountId)],
-		_time_slot: &O::TimeSlot,
+		offenders: &[(T::AccountId, T::AccountId)],
+		time_slot: &O::TimeSlot,
 	) -> bool {
-		false
+		let report_id = Self::offence_report_id::<O>(time_slot);
+		let known = <ReportedOffences<T>>::get(report_id);
+		offenders.iter().all(|(who, _)| known.contains(who))
 	}
 }
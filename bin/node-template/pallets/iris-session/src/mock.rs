@@ -0,0 +1,195 @@
+//! Mock runtime for unit-testing pallet-iris-session, in particular the bonding/slashing
+//! arithmetic (`concurrency_slash_fraction`, `slash_validator_bond`, `can_rejoin`) that backs
+//! the proof-of-pinning offence pipeline.
+
+#![cfg(test)]
+
+use crate as pallet_iris_session;
+use frame_support::{parameter_types, traits::ConstU32};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::{
+	testing::{Header, TestXt},
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+
+/// test-only offchain-worker signing key type; the crate itself has no runtime wired up to
+/// supply one, so the mock provides a minimal `app_crypto` identity to satisfy
+/// `Config::AuthorityId: AppCrypto<...>`
+pub mod crypto {
+	use sp_runtime::{app_crypto::{app_crypto, sr25519}, MultiSignature, MultiSigner};
+
+	pub const IRIS: sp_runtime::KeyTypeId = sp_runtime::KeyTypeId(*b"tiri");
+
+	app_crypto!(sr25519, IRIS);
+
+	pub struct TestAuthId;
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for TestAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+pub type AccountId = u64;
+pub type Balance = u64;
+pub type BlockNumber = u64;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Session: pallet_session::{Pallet, Call, Storage, Event, Config<T>},
+		IrisAssets: pallet_iris_assets::{Pallet, Call, Storage, Event<T>},
+		IrisSession: pallet_iris_session::{Pallet, Call, Storage, Event<T>, Config<T>, ValidateUnsigned},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: BlockNumber = 250;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type MaxLocks = ConstU32<50>;
+	type MaxReserves = ConstU32<50>;
+	type ReserveIdentifier = [u8; 8];
+}
+
+impl pallet_iris_assets::Config for Test {
+	type Event = Event;
+	type AssetId = u32;
+	type Currency = Balances;
+}
+
+/// no-op session key/handler wiring; these tests exercise bonding/slashing arithmetic directly
+/// and never drive a real session rotation through `pallet_session`
+pub struct TestSessionHandler;
+impl pallet_session::SessionHandler<AccountId> for TestSessionHandler {
+	const KEY_TYPE_IDS: &'static [sp_runtime::KeyTypeId] = &[];
+	fn on_genesis_session<Ks: sp_runtime::traits::OpaqueKeys>(_validators: &[(AccountId, Ks)]) {}
+	fn on_new_session<Ks: sp_runtime::traits::OpaqueKeys>(
+		_changed: bool,
+		_validators: &[(AccountId, Ks)],
+		_queued_validators: &[(AccountId, Ks)],
+	) {
+	}
+	fn on_disabled(_validator_index: u32) {}
+}
+
+sp_runtime::impl_opaque_keys! {
+	pub struct SessionKeys {}
+}
+
+parameter_types! {
+	pub const Period: BlockNumber = 10;
+	pub const Offset: BlockNumber = 0;
+}
+
+impl pallet_session::Config for Test {
+	type Event = Event;
+	type ValidatorId = AccountId;
+	type ValidatorIdOf = pallet_iris_session::ValidatorOf<Test>;
+	type ShouldEndSession = pallet_session::PeriodicSessions<Period, Offset>;
+	type NextSessionRotation = pallet_session::PeriodicSessions<Period, Offset>;
+	type SessionManager = IrisSession;
+	type SessionHandler = TestSessionHandler;
+	type Keys = SessionKeys;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MinAuthorities: u32 = 2;
+	pub const UnsignedPriority: u64 = 100;
+	pub const AuditThreshold: u32 = 3;
+	pub const AuditWindow: u32 = 10;
+	pub const PoolBond: Balance = 10;
+	pub const UnbondingDelay: u32 = 2;
+	pub const ValidatorBond: Balance = 100;
+	pub const MinorOffenceSlashFraction: Perbill = Perbill::from_percent(5);
+	pub const MajorOffenceSlashFraction: Perbill = Perbill::from_percent(50);
+	pub const RemovalDeferWindow: u32 = 1;
+	pub const OffenceCooldown: u32 = 3;
+}
+
+type Extrinsic = TestXt<Call, ()>;
+
+impl frame_system::offchain::SendTransactionTypes<Call> for Test {
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+impl pallet_iris_session::Config for Test {
+	type Event = Event;
+	type Call = Call;
+	type AddRemoveOrigin = frame_system::EnsureRoot<AccountId>;
+	type MinAuthorities = MinAuthorities;
+	type AuthorityId = crypto::TestAuthId;
+	type UnsignedPriority = UnsignedPriority;
+	type AuditThreshold = AuditThreshold;
+	type AuditWindow = AuditWindow;
+	type ReportOffence = IrisSession;
+	type Currency = Balances;
+	type PoolBond = PoolBond;
+	type UnbondingDelay = UnbondingDelay;
+	type WeightInfo = ();
+	type ValidatorBond = ValidatorBond;
+	type MinorOffenceSlashFraction = MinorOffenceSlashFraction;
+	type MajorOffenceSlashFraction = MajorOffenceSlashFraction;
+	type OnSlash = ();
+	type RemovalDeferWindow = RemovalDeferWindow;
+	type OffenceCooldown = OffenceCooldown;
+}
+
+/// builds a bare test externality with no accounts/balances pre-funded; most of the slashing
+/// arithmetic under test here reads/writes pallet storage directly and doesn't need genesis
+/// validators or funded accounts
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let mut ext = sp_io::TestExternalities::from(storage);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,121 @@
+//! Behavioral tests for the bonding/slashing arithmetic underpinning the proof-of-pinning
+//! offence pipeline: `concurrency_slash_fraction`'s clamping, `slash_validator_bond`'s
+//! incremental escalation across repeated reports for the same slot, and `can_rejoin`'s
+//! session-boundary math against `OffenceCooldown`.
+
+#![cfg(test)]
+
+use crate::mock::{new_test_ext, AccountId, Test};
+use crate::{Pallet, RemovalSchedule, SlotSlashed, ValidatorBonds};
+use frame_support::traits::Currency;
+use sp_runtime::Perbill;
+
+type IrisSession = Pallet<Test>;
+
+#[test]
+fn concurrency_slash_fraction_is_zero_at_threshold() {
+	new_test_ext().execute_with(|| {
+		// validator_count = 100 -> threshold = 100/10 + 1 = 11; 11 offenders is at, not over,
+		// the threshold, so the fraction stays zero
+		assert_eq!(IrisSession::concurrency_slash_fraction(11, 100), Perbill::zero());
+	});
+}
+
+#[test]
+fn concurrency_slash_fraction_climbs_past_threshold() {
+	new_test_ext().execute_with(|| {
+		// 20 offenders of 100: over_threshold = 20 - 11 = 9, fraction = 9*3/100 = 27%
+		assert_eq!(
+			IrisSession::concurrency_slash_fraction(20, 100),
+			Perbill::from_percent(27),
+		);
+	});
+}
+
+#[test]
+fn concurrency_slash_fraction_clamps_at_full_validator_set() {
+	new_test_ext().execute_with(|| {
+		// o == n: every validator offending in the same slot should saturate to a full slash,
+		// not overflow or panic on the `3*(o-threshold)` multiplication
+		assert_eq!(IrisSession::concurrency_slash_fraction(100, 100), Perbill::one());
+	});
+}
+
+#[test]
+fn concurrency_slash_fraction_handles_zero_validators() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(IrisSession::concurrency_slash_fraction(0, 0), Perbill::zero());
+	});
+}
+
+#[test]
+fn slash_validator_bond_escalates_incrementally_for_the_same_report() {
+	new_test_ext().execute_with(|| {
+		let who: AccountId = 1;
+		let report_id = sp_core::H256::repeat_byte(7);
+
+		<pallet_balances::Pallet<Test> as Currency<AccountId>>::make_free_balance_be(&who, 1_000);
+		<pallet_balances::Pallet<Test> as Currency<AccountId>>::reserve(&who, 1_000).unwrap();
+		<ValidatorBonds<Test>>::insert(&who, 1_000u64);
+
+		// first report: bring the cumulative slash up to 10% of the original 1_000 bond
+		IrisSession::slash_validator_bond(&report_id, &who, Perbill::from_percent(10));
+		assert_eq!(<ValidatorBonds<Test>>::get(&who), 900);
+		assert_eq!(<SlotSlashed<Test>>::get((&report_id, &who)), 100);
+
+		// second, larger report for the *same* report_id: only the incremental 20% (target 30%
+		// of the original bond minus the 10% already taken) should be slashed, not another 30%
+		IrisSession::slash_validator_bond(&report_id, &who, Perbill::from_percent(30));
+		assert_eq!(<ValidatorBonds<Test>>::get(&who), 700);
+		assert_eq!(<SlotSlashed<Test>>::get((&report_id, &who)), 300);
+
+		// a report whose fraction doesn't exceed what's already been slashed for this report_id
+		// is a no-op
+		IrisSession::slash_validator_bond(&report_id, &who, Perbill::from_percent(20));
+		assert_eq!(<ValidatorBonds<Test>>::get(&who), 700);
+		assert_eq!(<SlotSlashed<Test>>::get((&report_id, &who)), 300);
+	});
+}
+
+#[test]
+fn slash_validator_bond_is_noop_with_no_bond() {
+	new_test_ext().execute_with(|| {
+		let who: AccountId = 2;
+		let report_id = sp_core::H256::repeat_byte(9);
+
+		IrisSession::slash_validator_bond(&report_id, &who, Perbill::from_percent(50));
+		assert_eq!(<ValidatorBonds<Test>>::get(&who), 0);
+		assert_eq!(<SlotSlashed<Test>>::get((&report_id, &who)), 0);
+	});
+}
+
+#[test]
+fn can_rejoin_is_true_for_a_validator_never_removed() {
+	new_test_ext().execute_with(|| {
+		let who: AccountId = 3;
+		assert!(IrisSession::can_rejoin(&who));
+	});
+}
+
+#[test]
+fn can_rejoin_is_false_before_offence_cooldown_elapses() {
+	new_test_ext().execute_with(|| {
+		let who: AccountId = 4;
+		// OffenceCooldown is 3 sessions in the mock; removed in session 0, checked at session 2
+		<RemovalSchedule<Test>>::insert(&who, 0u32);
+		pallet_session::CurrentIndex::<Test>::put(2u32);
+
+		assert!(!IrisSession::can_rejoin(&who));
+	});
+}
+
+#[test]
+fn can_rejoin_is_true_once_offence_cooldown_elapses() {
+	new_test_ext().execute_with(|| {
+		let who: AccountId = 5;
+		<RemovalSchedule<Test>>::insert(&who, 0u32);
+		pallet_session::CurrentIndex::<Test>::put(3u32);
+
+		assert!(IrisSession::can_rejoin(&who));
+	});
+}
@@ -0,0 +1,173 @@
+//! Autogenerated weights for pallet_iris_session
+//!
+//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI, DO NOT EDIT BY HAND.
+//! Regenerate with the `benchmarking` module's `impl_benchmark_test_suite!`/`benchmarks!` output.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_iris_session.
+pub trait WeightInfo {
+	fn add_validator(v: u32) -> Weight;
+	fn remove_validator(v: u32) -> Weight;
+	fn add_validator_again(v: u32) -> Weight;
+	fn go_offline() -> Weight;
+	fn go_online() -> Weight;
+	fn request_join_storage_pool() -> Weight;
+	fn request_exit_storage_pool() -> Weight;
+	fn set_replication_factor() -> Weight;
+	fn submit_ipfs_add_results_unsigned() -> Weight;
+	fn submit_rpc_ready_unsigned() -> Weight;
+	fn submit_ipfs_identity_unsigned() -> Weight;
+	fn submit_pinning_audit_unsigned() -> Weight;
+}
+
+/// Weights for pallet_iris_session using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: IrisSession Validators (r:1 w:1)
+	// Storage: IrisSession ApprovedValidators (r:1 w:1)
+	fn add_validator(v: u32) -> Weight {
+		Weight::from_ref_time(15_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(25_000 as u64).saturating_mul(v as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	// Storage: IrisSession Validators (r:1 w:1)
+	// Storage: IrisSession ApprovedValidators (r:1 w:1)
+	fn remove_validator(v: u32) -> Weight {
+		Weight::from_ref_time(15_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(25_000 as u64).saturating_mul(v as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	// Storage: IrisSession ApprovedValidators (r:1 w:0)
+	// Storage: IrisSession Validators (r:1 w:1)
+	fn add_validator_again(v: u32) -> Weight {
+		Weight::from_ref_time(12_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(20_000 as u64).saturating_mul(v as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: IrisSession ApprovedValidators (r:1 w:0)
+	// Storage: IrisSession OutgoingValidators (r:1 w:1)
+	// Storage: IrisSession Validators (r:1 w:0)
+	fn go_offline() -> Weight {
+		Weight::from_ref_time(14_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: IrisSession ApprovedValidators (r:1 w:0)
+	// Storage: IrisSession Validators (r:1 w:1)
+	// Storage: IrisSession OutgoingValidators (r:1 w:1)
+	fn go_online() -> Weight {
+		Weight::from_ref_time(14_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	// Storage: IrisSession PoolBonds (r:1 w:1)
+	fn request_join_storage_pool() -> Weight {
+		Weight::from_ref_time(30_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	// Storage: IrisSession PoolBonds (r:1 w:0)
+	// Storage: IrisSession UnbondingRequests (r:0 w:1)
+	fn request_exit_storage_pool() -> Weight {
+		Weight::from_ref_time(12_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: IrisSession ReplicationFactor (r:0 w:1)
+	fn set_replication_factor() -> Weight {
+		Weight::from_ref_time(8_000_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: IrisSession AssignedCids (r:0 w:1)
+	// Storage: IrisSession PinReports (r:1 w:1)
+	// Storage: IrisSession PoolCids (r:1 w:1)
+	// Storage: IrisSession CidMetadata (r:0 w:1)
+	fn submit_ipfs_add_results_unsigned() -> Weight {
+		Weight::from_ref_time(25_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+	fn submit_rpc_ready_unsigned() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn submit_ipfs_identity_unsigned() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	// Storage: IrisSession MissingCidCounts (r:0 w:1)
+	// Storage: IrisSession PinReports (r:1 w:1)
+	fn submit_pinning_audit_unsigned() -> Weight {
+		Weight::from_ref_time(18_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	fn add_validator(v: u32) -> Weight {
+		Weight::from_ref_time(15_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(25_000 as u64).saturating_mul(v as u64))
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	fn remove_validator(v: u32) -> Weight {
+		Weight::from_ref_time(15_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(25_000 as u64).saturating_mul(v as u64))
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	fn add_validator_again(v: u32) -> Weight {
+		Weight::from_ref_time(12_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(20_000 as u64).saturating_mul(v as u64))
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn go_offline() -> Weight {
+		Weight::from_ref_time(14_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn go_online() -> Weight {
+		Weight::from_ref_time(14_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	fn request_join_storage_pool() -> Weight {
+		Weight::from_ref_time(30_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	fn request_exit_storage_pool() -> Weight {
+		Weight::from_ref_time(12_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn set_replication_factor() -> Weight {
+		Weight::from_ref_time(8_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn submit_ipfs_add_results_unsigned() -> Weight {
+		Weight::from_ref_time(25_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
+	fn submit_rpc_ready_unsigned() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn submit_ipfs_identity_unsigned() -> Weight {
+		Weight::from_ref_time(10_000_000 as u64)
+	}
+	fn submit_pinning_audit_unsigned() -> Weight {
+		Weight::from_ref_time(18_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+}